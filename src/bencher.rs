@@ -1,6 +1,8 @@
+use std::fmt;
 use std::future::Future;
+use std::ops::Sub;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::{Stats, Step};
 use crate::fmt_thousands_sep;
@@ -8,6 +10,260 @@ use crate::timing_future::TimingFuture;
 #[cfg(feature = "track-allocator")]
 use crate::track_allocator::GLOBAL;
 
+/// Identity function that the optimizer cannot see through.
+///
+/// Every benchmarked value is routed through `black_box` so LLVM can't prove
+/// the work is dead and delete the loop body, which would otherwise yield
+/// bogus sub-nanosecond timings. On toolchains new enough to ship
+/// [`std::hint::black_box`] we forward to it; the `legacy-black-box` feature
+/// swaps in an `#[inline(never)]` volatile fallback for older compilers.
+#[cfg(not(feature = "legacy-black-box"))]
+#[inline]
+pub fn black_box<T>(x: T) -> T {
+    std::hint::black_box(x)
+}
+
+#[cfg(feature = "legacy-black-box")]
+#[inline(never)]
+pub fn black_box<T>(x: T) -> T {
+    // Read the value back through a volatile load so the optimizer has to
+    // assume it escaped, then drop the original copy without running it twice.
+    unsafe {
+        let ret = std::ptr::read_volatile(&x);
+        std::mem::forget(x);
+        ret
+    }
+}
+
+/// A memory snapshot: total bytes requested, the high watermark reached, and
+/// how many distinct allocations were made. A snapshot taken after a step
+/// covers all of its iterations; [`MemoryUsage::per_iter`] averages it.
+///
+/// Two snapshots can be subtracted to report a delta, and the `Sub` saturates
+/// so a noisy sample can never underflow.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    pub allocated_bytes: usize,
+    pub peak_bytes: usize,
+    pub alloc_count: usize,
+}
+
+impl MemoryUsage {
+    /// Average the allocation totals over `n` iterations. `peak_bytes` is a
+    /// high watermark, so it is left untouched.
+    pub fn per_iter(self, n: usize) -> MemoryUsage {
+        let n = n.max(1);
+        MemoryUsage {
+            allocated_bytes: self.allocated_bytes / n,
+            peak_bytes: self.peak_bytes,
+            alloc_count: self.alloc_count / n,
+        }
+    }
+}
+
+impl Sub for MemoryUsage {
+    type Output = MemoryUsage;
+
+    fn sub(self, rhs: MemoryUsage) -> MemoryUsage {
+        MemoryUsage {
+            allocated_bytes: self.allocated_bytes.saturating_sub(rhs.allocated_bytes),
+            peak_bytes: self.peak_bytes.saturating_sub(rhs.peak_bytes),
+            alloc_count: self.alloc_count.saturating_sub(rhs.alloc_count),
+        }
+    }
+}
+
+impl fmt::Display for MemoryUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocs, {} bytes/iter, peak {}",
+            fmt_thousands_sep(self.alloc_count, ','),
+            fmt_thousands_sep(self.allocated_bytes, ','),
+            fmt_thousands_sep(self.peak_bytes, ','),
+        )
+    }
+}
+
+/// Winsorized median, MAD and standard deviation for one measurement channel.
+///
+/// These complement [`Stats`] (which is defined in another module outside this
+/// snapshot and so isn't editable here); they are derived once after sampling
+/// and cached on the [`Bencher`] so the report never re-clones and re-sorts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChannelStats {
+    pub median: u128,
+    pub mad: u128,
+    pub stddev: u128,
+}
+
+/// Selects how a finished benchmark is rendered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable colored output (the default).
+    Pretty,
+    /// One line of newline-delimited JSON per record.
+    Json,
+    /// One CSV row per record.
+    Csv,
+}
+
+/// A fully-resolved benchmark result, ready for machine-readable export.
+///
+/// Times are in nanoseconds and throughput in MB/s. The `mem_*` fields are the
+/// per-step peak memory watermark in bytes (not per-iteration); the genuine
+/// per-iteration figures are `allocated_bytes` and `alloc_count`.
+pub struct Record {
+    pub name: String,
+    pub times_min: u128,
+    pub times_max: u128,
+    pub times_average: u128,
+    pub times_median: u128,
+    /// Per-step peak memory watermark (min/max/avg/median across steps), bytes.
+    pub mem_min: usize,
+    pub mem_max: usize,
+    pub mem_average: usize,
+    pub mem_median: usize,
+    /// Mean bytes allocated per iteration.
+    pub allocated_bytes: usize,
+    /// Mean allocations per iteration.
+    pub alloc_count: usize,
+    pub bytes: usize,
+    pub throughput_mb_s: f64,
+    pub count: usize,
+    pub n: usize,
+    pub avg_polls: usize,
+}
+
+impl Record {
+    /// Column order shared by [`Record::to_csv_row`] and the aggregated export.
+    pub const CSV_HEADER: &'static str = "name,times_min,times_max,times_average,times_median,\
+mem_min,mem_max,mem_average,mem_median,allocated_bytes,alloc_count,bytes,throughput_mb_s,count,n,avg_polls";
+
+    /// Serialize to a single NDJSON line (no trailing newline).
+    pub fn to_json_line(&self) -> String {
+        format!(
+            "{{\"name\":{},\"times_min\":{},\"times_max\":{},\"times_average\":{},\
+\"times_median\":{},\"mem_min\":{},\"mem_max\":{},\"mem_average\":{},\"mem_median\":{},\
+\"allocated_bytes\":{},\"alloc_count\":{},\
+\"bytes\":{},\"throughput_mb_s\":{:.4},\"count\":{},\"n\":{},\"avg_polls\":{}}}",
+            json_string(&self.name),
+            self.times_min,
+            self.times_max,
+            self.times_average,
+            self.times_median,
+            self.mem_min,
+            self.mem_max,
+            self.mem_average,
+            self.mem_median,
+            self.allocated_bytes,
+            self.alloc_count,
+            self.bytes,
+            self.throughput_mb_s,
+            self.count,
+            self.n,
+            self.avg_polls,
+        )
+    }
+
+    /// Serialize to a single CSV row matching [`Record::CSV_HEADER`].
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{:.4},{},{},{}",
+            csv_field(&self.name),
+            self.times_min,
+            self.times_max,
+            self.times_average,
+            self.times_median,
+            self.mem_min,
+            self.mem_max,
+            self.mem_average,
+            self.mem_median,
+            self.allocated_bytes,
+            self.alloc_count,
+            self.bytes,
+            self.throughput_mb_s,
+            self.count,
+            self.n,
+            self.avg_polls,
+        )
+    }
+}
+
+/// Collects many benchers' [`Record`]s for a single aggregated export, e.g. at
+/// program exit for CI trend-tracking or to feed a dashboard.
+#[derive(Default)]
+pub struct Report {
+    records: Vec<Record>,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snapshot a finished bencher into the report.
+    pub fn add(&mut self, bencher: &Bencher) {
+        self.records.push(bencher.record());
+    }
+
+    /// Render every collected record as one document in `format`: an NDJSON
+    /// stream, a CSV table with a leading header row, or (for `Pretty`) an
+    /// empty string since that variant has no aggregated text form.
+    pub fn to_document(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Json => self
+                .records
+                .iter()
+                .map(Record::to_json_line)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            OutputFormat::Csv => {
+                let mut out = String::from(Record::CSV_HEADER);
+                for record in &self.records {
+                    out.push('\n');
+                    out.push_str(&record.to_csv_row());
+                }
+                out
+            }
+            OutputFormat::Pretty => String::new(),
+        }
+    }
+
+    /// Print the aggregated document to stdout.
+    pub fn emit(&self, format: OutputFormat) {
+        println!("{}", self.to_document(format));
+    }
+}
+
+/// Escape a string as a JSON string literal (including the surrounding quotes).
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Quote a CSV field if it contains a comma, quote or newline.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
 pub struct Bencher {
     pub name: String,
     pub count: usize,
@@ -15,9 +271,169 @@ pub struct Bencher {
     pub bytes: usize,
     pub n: usize,
     pub poll: usize,
+    /// Lower bound on the wall time each step must accumulate before it is
+    /// sampled; `n` grows geometrically until a step crosses it.
+    pub min_time: Duration,
+    /// Upper bound that caps calibration so a slow operation can't run away.
+    pub max_time: Duration,
+    /// Fixed inner iteration count, overriding time-based calibration.
+    pub sample_size: Option<usize>,
     pub format_fn: fn(&Stats, &Bencher),
 
-    pub mem_track: (&'static AtomicUsize, &'static AtomicUsize)
+    /// Samples after winsorizing both channels, computed once after sampling so
+    /// [`Stats`] and the channel stats below are derived without re-sorting.
+    pub winsorized: Vec<Step>,
+    /// Median/MAD/std-dev of the winsorized per-step times, in nanoseconds.
+    pub time_stats: ChannelStats,
+    /// Median/MAD/std-dev of the winsorized per-step peak memory, in bytes.
+    pub mem_stats: ChannelStats,
+
+    /// Per-step memory usage, one [`MemoryUsage`] per sample, averaged per
+    /// iteration (except `peak_bytes`, which is the step's high watermark).
+    pub mem_usage: Vec<MemoryUsage>,
+
+    // (current_bytes, peak_bytes, alloc_count, allocated_bytes)
+    pub mem_track: (
+        &'static AtomicUsize,
+        &'static AtomicUsize,
+        &'static AtomicUsize,
+        &'static AtomicUsize,
+    ),
+}
+
+/// Linear-interpolated percentile of a pre-sorted slice, mirroring the
+/// statistic libtest reports. `pct` is given in the `0.0..=100.0` range.
+fn percentile(sorted: &[u128], pct: f64) -> u128 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (pct / 100.0) * (sorted.len() - 1) as f64;
+    let lo = rank.floor() as usize;
+    let hi = rank.ceil() as usize;
+    let frac = rank - lo as f64;
+    let interp = sorted[lo] as f64 + frac * (sorted[hi] as f64 - sorted[lo] as f64);
+    interp.round() as u128
+}
+
+/// Median of a pre-sorted slice.
+fn median(sorted: &[u128]) -> u128 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Median absolute deviation around `med`.
+fn mad(sorted: &[u128], med: u128) -> u128 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let mut dev: Vec<u128> = sorted.iter().map(|&v| v.abs_diff(med)).collect();
+    dev.sort_unstable();
+    median(&dev)
+}
+
+/// Sample standard deviation (Bessel-corrected, `n - 1`) of a slice, rounded
+/// to the nearest whole unit. Returns `0` for fewer than two samples.
+fn stddev(values: &[u128]) -> u128 {
+    if values.len() < 2 {
+        return 0;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().map(|&v| v as f64).sum::<f64>() / n;
+    let var = values
+        .iter()
+        .map(|&v| {
+            let d = v as f64 - mean;
+            d * d
+        })
+        .sum::<f64>()
+        / (n - 1.0);
+    var.sqrt().round() as u128
+}
+
+/// Winsorize `values` at 5% in place: clamp every sample below the 5th
+/// percentile up to it and every sample above the 95th percentile down to it.
+/// Samples are clamped, never discarded, so the count is preserved.
+fn winsorize(values: &mut [u128]) {
+    if values.is_empty() {
+        return;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let lo = percentile(&sorted, 5.0);
+    let hi = percentile(&sorted, 95.0);
+    for v in values.iter_mut() {
+        *v = (*v).clamp(lo, hi);
+    }
+}
+
+/// A single benchmark registered through [`benchmark_group!`], pairing a
+/// filterable name with the function that constructs, runs and finishes it.
+pub struct NamedBench {
+    pub name: &'static str,
+    pub run: fn(),
+}
+
+/// Entry point for a `harness = false` bench binary.
+///
+/// Parses `args` (typically `std::env::args().skip(1)`) and runs the
+/// registered benches whose `name` contains any supplied substring filter —
+/// or all of them when no filter is given. `--list` prints the matching names
+/// without running anything, mirroring the selective execution offered by the
+/// `bencher` crate.
+pub fn run_all<I: IntoIterator<Item = String>>(benches: &[NamedBench], args: I) {
+    let args: Vec<String> = args.into_iter().collect();
+    let list = args.iter().any(|a| a == "--list");
+    let filters: Vec<&str> = args
+        .iter()
+        .filter(|a| !a.starts_with("--"))
+        .map(String::as_str)
+        .collect();
+
+    for bench in benches {
+        let matched = filters.is_empty() || filters.iter().any(|f| bench.name.contains(f));
+        if !matched {
+            continue;
+        }
+        if list {
+            println!("{}", bench.name);
+        } else {
+            (bench.run)();
+        }
+    }
+}
+
+/// Collect a set of benches into a named group function returning their
+/// [`NamedBench`] registrations.
+#[macro_export]
+macro_rules! benchmark_group {
+    ($group:ident, $($bench:path),+ $(,)?) => {
+        pub fn $group() -> ::std::vec::Vec<$crate::bencher::NamedBench> {
+            ::std::vec![
+                $( $crate::bencher::NamedBench { name: stringify!($bench), run: $bench } ),+
+            ]
+        }
+    };
+}
+
+/// Generate a `main` that gathers the listed groups and hands them to
+/// [`run_all`], so a file in `benches/` with `harness = false` gets selective
+/// CLI-driven execution for free.
+#[macro_export]
+macro_rules! benchmark_main {
+    ($($group:path),+ $(,)?) => {
+        fn main() {
+            let mut benches = ::std::vec::Vec::new();
+            $( benches.extend($group()); )+
+            $crate::bencher::run_all(&benches, ::std::env::args().skip(1));
+        }
+    };
 }
 
 impl Bencher {
@@ -30,14 +446,29 @@ impl Bencher {
             bytes,
             n: 0,
             poll: 0,
+            min_time: Duration::from_millis(1),
+            max_time: Duration::from_secs(5),
+            sample_size: None,
             format_fn: |s, b| Self::default_format(s, b),
+            winsorized: Vec::new(),
+            time_stats: ChannelStats::default(),
+            mem_stats: ChannelStats::default(),
+            mem_usage: Vec::with_capacity(count),
 
-            mem_track: (GLOBAL.counter(), GLOBAL.peak())
+            mem_track: (GLOBAL.counter(), GLOBAL.peak(), GLOBAL.alloc_count(), GLOBAL.allocated())
         }
     }
 
     #[cfg(not(feature = "track-allocator"))]
-    pub fn new(name: impl AsRef<str>, count: usize, bytes: usize, counter: &'static AtomicUsize, peak: &'static AtomicUsize) -> Self {
+    pub fn new(
+        name: impl AsRef<str>,
+        count: usize,
+        bytes: usize,
+        counter: &'static AtomicUsize,
+        peak: &'static AtomicUsize,
+        alloc_count: &'static AtomicUsize,
+        allocated: &'static AtomicUsize,
+    ) -> Self {
         Bencher {
             name: name.as_ref().to_owned(),
             count,
@@ -45,42 +476,139 @@ impl Bencher {
             bytes,
             n: 0,
             poll: 0,
+            min_time: Duration::from_millis(1),
+            max_time: Duration::from_secs(5),
+            sample_size: None,
             format_fn: |s, b| Self::default_format(s, b),
+            winsorized: Vec::new(),
+            time_stats: ChannelStats::default(),
+            mem_stats: ChannelStats::default(),
+            mem_usage: Vec::with_capacity(count),
+
+            mem_track: (counter, peak, alloc_count, allocated)
+        }
+    }
+
+    /// Keep growing the inner iteration count until each step accumulates at
+    /// least this much wall time, giving fast-but-variable operations a stable
+    /// sample instead of a fixed 1 ms window.
+    pub fn with_min_time(mut self, min_time: Duration) -> Self {
+        self.min_time = min_time;
+        self
+    }
+
+    /// Cap calibration so a slow operation can't spin up a huge `n`.
+    pub fn with_max_time(mut self, max_time: Duration) -> Self {
+        self.max_time = max_time;
+        self
+    }
 
-            mem_track: (counter, peak)
+    /// Pin the inner iteration count, bypassing time-based calibration.
+    pub fn with_sample_size(mut self, sample_size: usize) -> Self {
+        self.sample_size = Some(sample_size);
+        self
+    }
+
+    /// Pick the inner iteration count `n` for sync benches: honour an explicit
+    /// `sample_size`, otherwise grow `n` geometrically until a trial run
+    /// crosses `min_time`, stopping early once `max_time` is reached.
+    fn calibrate<T>(&self, f: &mut impl FnMut() -> T) -> usize {
+        if let Some(n) = self.sample_size {
+            return n.max(1);
+        }
+        let mut n = 1usize;
+        loop {
+            let elapsed = Duration::from_nanos(self.bench_once(f, n).0 as u64);
+            if elapsed >= self.min_time || elapsed >= self.max_time {
+                return n;
+            }
+            n = n.saturating_mul(2);
+        }
+    }
+
+    /// Swap the output format. Defaults to [`OutputFormat::Pretty`]; select
+    /// `Json` or `Csv` for machine-readable, CI-friendly records.
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format_fn = match format {
+            OutputFormat::Pretty => |s, b| Self::default_format(s, b),
+            OutputFormat::Json => |s, b| Self::json_format(s, b),
+            OutputFormat::Csv => |s, b| Self::csv_format(s, b),
+        };
+        self
+    }
+
+    /// Snapshot this bencher's winsorized results into a [`Record`].
+    pub fn record(&self) -> Record {
+        let stats = Stats::from(&self.winsorized);
+        let times_average = stats.times_average as u128;
+        let mem = self.avg_memory_usage();
+        Record {
+            name: self.name.clone(),
+            times_min: stats.times_min as u128,
+            times_max: stats.times_max as u128,
+            times_average,
+            times_median: self.time_stats.median,
+            mem_min: stats.mem_min,
+            mem_max: stats.mem_max,
+            mem_average: stats.mem_average,
+            mem_median: self.mem_stats.median as usize,
+            allocated_bytes: mem.allocated_bytes,
+            alloc_count: mem.alloc_count,
+            bytes: self.bytes,
+            throughput_mb_s: (self.bytes as f64
+                * (1_000_000_000f64 / times_average.max(1) as f64))
+                / 1_000_000f64,
+            count: self.count,
+            n: self.n,
+            avg_polls: self.poll,
         }
     }
 
     // (time, memory_usage)
-    pub fn bench_once<T>(&self, f: &mut impl FnMut() -> T, n: usize) -> (u128, usize) {
+    pub fn bench_once<T>(&self, f: &mut impl FnMut() -> T, n: usize) -> (u128, MemoryUsage) {
         let now = Instant::now();
         self.reset_mem();
 
         for _ in 0..n {
-            let _output = f();
+            black_box(f());
         }
 
-        (now.elapsed().as_nanos(), self.get_mem_peak())
+        (now.elapsed().as_nanos(), self.memory_usage())
     }
 
     pub fn iter<T>(&mut self, mut f: impl FnMut() -> T) {
-        let single = self.bench_once(&mut f, 1).0;
-        // 1_000_000ns : 1ms
-        self.n = (1_000_000 / single.max(1)).max(1) as usize;
+        self.n = self.calibrate(&mut f);
         (0..self.count).for_each(|_| {
             let res = self.bench_once(&mut f, self.n);
             self.steps.push(Step {
                 time: res.0 / self.n as u128,
-                mem: res.1 / self.n
-            })
+                mem: res.1.peak_bytes
+            });
+            self.mem_usage.push(res.1.per_iter(self.n));
         });
+        self.summarize();
     }
 
     pub fn async_iter<'a, T, Fut: Future<Output=T>>(&'a mut self, mut f: impl FnMut() -> Fut + 'a) -> impl Future + 'a {
         async move {
-            let single = TimingFuture::new(f()).await.elapsed_time.as_nanos();
-            // 1_000_000ns : 1ms
-            self.n = (1_000_000 / single.max(1)).max(1) as usize;
+            // Grow `n` geometrically until a trial step crosses `min_time`
+            // (capped by `max_time`), unless a fixed sample size is pinned.
+            self.n = if let Some(n) = self.sample_size {
+                n.max(1)
+            } else {
+                let mut n = 1usize;
+                loop {
+                    let mut elapsed = 0u128;
+                    for _ in 0..n {
+                        elapsed += TimingFuture::new(f()).await.elapsed_time.as_nanos();
+                    }
+                    let elapsed = Duration::from_nanos(elapsed as u64);
+                    if elapsed >= self.min_time || elapsed >= self.max_time {
+                        break n;
+                    }
+                    n = n.saturating_mul(2);
+                }
+            };
 
             let mut polls = Vec::with_capacity(self.count);
 
@@ -89,48 +617,135 @@ impl Bencher {
                 self.reset_mem();
                 
                 for _ in 0..self.n {
-                    let tf = TimingFuture::new(f()).await;
+                    let tf = black_box(TimingFuture::new(f()).await);
                     mtime += tf.elapsed_time.as_nanos();
                     polls.push(tf.poll);
                 }
 
+                let usage = self.memory_usage();
                 self.steps.push(Step {
                     time: mtime / self.n as u128,
-                    mem: self.get_mem_peak() / self.n
+                    mem: usage.peak_bytes
                 });
+                self.mem_usage.push(usage.per_iter(self.n));
             }
 
             self.poll = polls.iter().sum::<usize>() / polls.len();
+            self.summarize();
         }
     }
 
     pub fn finish(&self) {
-        let stats = Stats::from(&self.steps);
+        let stats = Stats::from(&self.winsorized);
         (self.format_fn)(&stats, self)
     }
 
+    /// Per-step samples with their `time` and `mem` channels independently
+    /// winsorized at 5%, so a lone GC pause or scheduler hiccup can't inflate
+    /// the average or spread without throwing away sample count.
+    pub fn winsorized_steps(&self) -> Vec<Step> {
+        let mut times: Vec<u128> = self.steps.iter().map(|s| s.time).collect();
+        let mut mems: Vec<u128> = self.steps.iter().map(|s| s.mem as u128).collect();
+        winsorize(&mut times);
+        winsorize(&mut mems);
+        times
+            .into_iter()
+            .zip(mems)
+            .map(|(time, mem)| Step { time, mem: mem as usize })
+            .collect()
+    }
+
+    /// Winsorize the samples once and cache them along with each channel's
+    /// median, MAD and standard deviation, so `finish`/`record`/`default_format`
+    /// all read the same precomputed figures instead of re-cloning and
+    /// re-sorting the samples on every call.
+    pub fn summarize(&mut self) {
+        self.winsorized = self.winsorized_steps();
+
+        let mut times: Vec<u128> = self.winsorized.iter().map(|s| s.time).collect();
+        let mut mems: Vec<u128> = self.winsorized.iter().map(|s| s.mem as u128).collect();
+        times.sort_unstable();
+        mems.sort_unstable();
+
+        let time_median = median(&times);
+        let mem_median = median(&mems);
+
+        self.time_stats = ChannelStats {
+            median: time_median,
+            mad: mad(&times, time_median),
+            stddev: stddev(&times),
+        };
+        self.mem_stats = ChannelStats {
+            median: mem_median,
+            mad: mad(&mems, mem_median),
+            stddev: stddev(&mems),
+        };
+    }
+
     pub fn reset_mem(&self) {
         self.mem_track.0.store(0, Ordering::SeqCst);
         self.mem_track.1.store(0, Ordering::SeqCst);
+        self.mem_track.2.store(0, Ordering::SeqCst);
+        self.mem_track.3.store(0, Ordering::SeqCst);
     }
 
     pub fn get_mem_peak(&self) -> usize {
         self.mem_track.1.load(Ordering::SeqCst)
     }
 
+    /// Current allocator counters as a [`MemoryUsage`] snapshot.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            allocated_bytes: self.mem_track.3.load(Ordering::SeqCst),
+            peak_bytes: self.mem_track.1.load(Ordering::SeqCst),
+            alloc_count: self.mem_track.2.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Mean per-iteration memory usage across all sampled steps.
+    pub fn avg_memory_usage(&self) -> MemoryUsage {
+        if self.mem_usage.is_empty() {
+            return MemoryUsage::default();
+        }
+        let len = self.mem_usage.len();
+        let sum = self.mem_usage.iter().fold(MemoryUsage::default(), |acc, u| MemoryUsage {
+            allocated_bytes: acc.allocated_bytes + u.allocated_bytes,
+            peak_bytes: acc.peak_bytes.max(u.peak_bytes),
+            alloc_count: acc.alloc_count + u.alloc_count,
+        });
+        MemoryUsage {
+            allocated_bytes: sum.allocated_bytes / len,
+            peak_bytes: sum.peak_bytes,
+            alloc_count: sum.alloc_count / len,
+        }
+    }
+
     fn default_format(stats: &Stats, bencher: &Bencher) {
+        let time = &bencher.time_stats;
+        let mem = &bencher.mem_stats;
         bunt::println!(
             "{[bg:white+blue+bold]} ... {[green+underline]} ns/iter (+/- {[red+underline]}) = {[yellow+underline]:.2} MB/s\
-            \n\t memory usage: {[green+underline]} bytes/iter (+/- {[red+underline]})\
+            \n\t median: {[green+underline]} ns/iter (MAD {[red+underline]})\
+            \n\t peak memory: {[green+underline]} bytes/step (+/- {[red+underline]})\
+            \n\t median: {[green+underline]} bytes/step (MAD {[red+underline]})\
+            \n\t {[cyan]}\
             \n\t @Total: {[magenta]} * {[white]} iters\
             {[bold]}",
              &bencher.name,
              fmt_thousands_sep(stats.times_average, ','),
-             fmt_thousands_sep(stats.times_max - stats.times_min, ','),
+             fmt_thousands_sep(time.stddev, ','),
              (bencher.bytes as f64 * (1_000_000_000f64 / stats.times_average as f64)) / 1000f64 / 1000f64,
 
+             fmt_thousands_sep(time.median, ','),
+             fmt_thousands_sep(time.mad, ','),
+
              fmt_thousands_sep(stats.mem_average, ','),
-             fmt_thousands_sep(stats.mem_max - stats.mem_min, ','),
+             fmt_thousands_sep(mem.stddev as usize, ','),
+
+             fmt_thousands_sep(mem.median as usize, ','),
+             fmt_thousands_sep(mem.mad as usize, ','),
+
+             bencher.avg_memory_usage(),
 
              bencher.count,
              bencher.n,
@@ -145,4 +760,12 @@ impl Bencher {
              },
         );
     }
+
+    fn json_format(_stats: &Stats, bencher: &Bencher) {
+        println!("{}", bencher.record().to_json_line());
+    }
+
+    fn csv_format(_stats: &Stats, bencher: &Bencher) {
+        println!("{}", bencher.record().to_csv_row());
+    }
 }
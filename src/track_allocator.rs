@@ -0,0 +1,105 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A [`GlobalAlloc`] shim over the [`System`] allocator that records enough to
+/// describe a benchmark's memory behaviour: the bytes currently live, their
+/// high watermark, how many allocations were made and the total bytes
+/// requested. A [`Bencher`](crate::Bencher) zeroes these counters before each
+/// step and reads them after it to attribute usage per iteration.
+pub struct TrackAllocator {
+    /// Bytes currently live (incremented on alloc, decremented on dealloc).
+    counter: AtomicUsize,
+    /// High watermark of `counter`.
+    peak: AtomicUsize,
+    /// Number of allocations made: each alloc, and each realloc that does not
+    /// shrink the block.
+    alloc_count: AtomicUsize,
+    /// Total bytes ever requested, never decremented.
+    allocated: AtomicUsize,
+}
+
+impl TrackAllocator {
+    pub const fn new() -> Self {
+        TrackAllocator {
+            counter: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            alloc_count: AtomicUsize::new(0),
+            allocated: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn counter(&'static self) -> &'static AtomicUsize {
+        &self.counter
+    }
+
+    pub fn peak(&'static self) -> &'static AtomicUsize {
+        &self.peak
+    }
+
+    pub fn alloc_count(&'static self) -> &'static AtomicUsize {
+        &self.alloc_count
+    }
+
+    pub fn allocated(&'static self) -> &'static AtomicUsize {
+        &self.allocated
+    }
+
+    /// Account for `size` freshly requested bytes: bump the allocation count
+    /// and running total, then raise the live counter and peak watermark.
+    fn on_grow(&self, size: usize) {
+        self.alloc_count.fetch_add(1, Ordering::SeqCst);
+        self.allocated.fetch_add(size, Ordering::SeqCst);
+        let live = self.counter.fetch_add(size, Ordering::SeqCst) + size;
+        self.peak.fetch_max(live, Ordering::SeqCst);
+    }
+
+    /// Account for `size` freed bytes without underflowing the live counter:
+    /// `reset_mem` zeroes the counter at the start of each step while earlier
+    /// allocations may still be live, so a free of memory that was
+    /// allocated *before* the reset must clamp at zero rather than wrap around
+    /// near `usize::MAX` and poison the peak watermark on the next `on_grow`.
+    fn on_shrink(&self, size: usize) {
+        let _ = self
+            .counter
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |live| {
+                Some(live.saturating_sub(size))
+            });
+    }
+}
+
+impl Default for TrackAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl GlobalAlloc for TrackAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            self.on_grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.on_shrink(layout.size());
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            let old = layout.size();
+            if new_size >= old {
+                self.on_grow(new_size - old);
+            } else {
+                self.on_shrink(old - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+pub static GLOBAL: TrackAllocator = TrackAllocator::new();